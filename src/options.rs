@@ -2,6 +2,8 @@ use {
   super::*,
   bitcoincore_rpc::{Auth, Client},
   clap::ValueEnum,
+  serde::Deserialize,
+  std::{collections::BTreeMap, env, ffi::OsStr, fmt},
 };
 
 #[derive(Debug, Parser)]
@@ -15,17 +17,169 @@ pub(crate) struct Options {
   cookie_file: Option<PathBuf>,
   #[clap(long, help = "Connect to Bitcoin Core RPC at <RPC_URL>.")]
   rpc_url: Option<String>,
-  #[clap(long, arg_enum, default_value = "mainnet", help = "Index <CHAIN>.")]
-  pub(crate) chain: Chain,
+  #[clap(long, arg_enum, help = "Index <CHAIN>. [default: mainnet]")]
+  chain: Option<Chain>,
   #[clap(long, help = "Store index in <DATA_DIR>.")]
   data_dir: Option<PathBuf>,
   #[clap(long, help = "Load Bitcoin Core data dir from <BITCOIN_DATA_DIR>.")]
   bitcoin_data_dir: Option<PathBuf>,
   #[clap(long, help = "Limit index to <HEIGHT_LIMIT> blocks.")]
   pub(crate) height_limit: Option<u64>,
+  #[clap(long, help = "Load config from <CONFIG>. [default: <DATA_DIR>/config.yaml]")]
+  config: Option<PathBuf>,
+  #[clap(
+    long,
+    help = "Look for config file in <CONFIG_DIR>. [default: <DATA_DIR>]"
+  )]
+  config_dir: Option<PathBuf>,
+  #[clap(
+    long,
+    help = "Authenticate to Bitcoin Core RPC with <BITCOIN_RPC_USERNAME>."
+  )]
+  bitcoin_rpc_username: Option<String>,
+  #[clap(
+    long,
+    help = "Authenticate to Bitcoin Core RPC with <BITCOIN_RPC_PASSWORD>."
+  )]
+  bitcoin_rpc_password: Option<String>,
+  #[clap(
+    long,
+    help = "Load Bitcoin Core RPC settings from <BITCOIN_CONF>. [default: bitcoin.conf in <BITCOIN_DATA_DIR>]"
+  )]
+  bitcoin_conf: Option<PathBuf>,
+}
+
+// The credentials ord will try, in order, to authenticate to Bitcoin Core's
+// RPC server with.
+#[derive(Debug, PartialEq)]
+pub(crate) enum RpcAuth {
+  Cookie(PathBuf),
+  UserPass(String, String),
+}
+
+impl RpcAuth {
+  fn into_auth(self) -> Auth {
+    match self {
+      Self::Cookie(cookie_file) => Auth::CookieFile(cookie_file),
+      Self::UserPass(username, password) => Auth::UserPass(username, password),
+    }
+  }
+}
+
+impl fmt::Display for RpcAuth {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Self::Cookie(cookie_file) => write!(f, "credentials from `{}`", cookie_file.display()),
+      Self::UserPass(..) => write!(f, "username and password"),
+    }
+  }
+}
+
+// Layered with bitcoin.conf (lowest), the config file, `ORD_`-prefixed
+// environment variables, and explicit CLI flags (highest). Every field is
+// optional so that a layer which doesn't set a given value falls through to
+// the next. `data_dir` and `bitcoin_data_dir` are deliberately absent: they
+// are locators used to find the config file and `bitcoin.conf` in the first
+// place, so neither can itself depend on `Settings` (see their accessors
+// below).
+#[derive(Debug, PartialEq, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Settings {
+  chain: Option<Chain>,
+  cookie_file: Option<PathBuf>,
+  max_index_size: Option<Bytes>,
+  rpc_url: Option<String>,
+  height_limit: Option<u64>,
+}
+
+impl Settings {
+  pub(crate) fn load(options: &Options) -> Result<Settings> {
+    Ok(
+      Self::from_options(options)
+        .merge(Self::from_env()?)
+        .merge(Self::from_config_file(options)?)
+        .merge(Self::from_bitcoin_conf(options)?),
+    )
+  }
+
+  fn merge(self, lower_priority: Settings) -> Settings {
+    Settings {
+      chain: self.chain.or(lower_priority.chain),
+      cookie_file: self.cookie_file.or(lower_priority.cookie_file),
+      max_index_size: self.max_index_size.or(lower_priority.max_index_size),
+      rpc_url: self.rpc_url.or(lower_priority.rpc_url),
+      height_limit: self.height_limit.or(lower_priority.height_limit),
+    }
+  }
+
+  fn from_options(options: &Options) -> Settings {
+    Settings {
+      chain: options.chain,
+      cookie_file: options.cookie_file.clone(),
+      max_index_size: options.max_index_size,
+      rpc_url: options.rpc_url.clone(),
+      height_limit: options.height_limit,
+    }
+  }
+
+  fn from_env() -> Result<Settings> {
+    fn parse<T: std::str::FromStr>(name: &str) -> Result<Option<T>>
+    where
+      T::Err: std::fmt::Display,
+    {
+      env::var(name)
+        .ok()
+        .map(|value| value.parse().map_err(|err| anyhow!("{err}")))
+        .transpose()
+        .with_context(|| format!("failed to parse `{name}` environment variable"))
+    }
+
+    let chain = env::var("ORD_CHAIN")
+      .ok()
+      .map(|value| <Chain as ValueEnum>::from_str(&value, true).map_err(|err| anyhow!(err)))
+      .transpose()
+      .with_context(|| "failed to parse `ORD_CHAIN` environment variable")?;
+
+    Ok(Settings {
+      chain,
+      cookie_file: env::var_os("ORD_COOKIE_FILE").map(PathBuf::from),
+      max_index_size: parse("ORD_MAX_INDEX_SIZE")?,
+      rpc_url: env::var("ORD_RPC_URL").ok(),
+      height_limit: parse("ORD_HEIGHT_LIMIT")?,
+    })
+  }
+
+  fn from_config_file(options: &Options) -> Result<Settings> {
+    let path = match options.config_path()? {
+      Some(path) => path,
+      None => return Ok(Settings::default()),
+    };
+
+    let content = fs::read_to_string(&path)
+      .with_context(|| format!("failed to read config file `{}`", path.display()))?;
+
+    if path.extension().and_then(OsStr::to_str) == Some("toml") {
+      toml::from_str(&content).map_err(anyhow::Error::from)
+    } else {
+      serde_yaml::from_str(&content).map_err(anyhow::Error::from)
+    }
+    .with_context(|| format!("failed to parse config file `{}`", path.display()))
+  }
+
+  // `bitcoin.conf`, unlike ord's own config file, only ever supplies an
+  // `rpc_url`; auth derived from `rpcuser`/`rpcpassword` is handled
+  // separately by `Options::rpc_auth`, and `txindex` is validated by
+  // `Options::require_txindex`.
+  fn from_bitcoin_conf(options: &Options) -> Result<Settings> {
+    Ok(Settings {
+      rpc_url: options.bitcoin_conf_rpc_url()?,
+      ..Settings::default()
+    })
+  }
 }
 
-#[derive(ValueEnum, Copy, Clone, Debug)]
+#[derive(ValueEnum, Copy, Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
 pub(crate) enum Chain {
   Main,
   Mainnet,
@@ -51,64 +205,266 @@ impl Chain {
       other => data_dir.join(other.to_string()),
     }
   }
+
+  // The subdirectory Bitcoin Core itself stores per-network data under.
+  // This is independent of ord's own `data_dir` layout (see
+  // `join_network_with_data_dir`); notably, Core stores testnet data under
+  // `testnet3`, not `testnet`.
+  pub(crate) fn core_data_dir_name(self) -> &'static str {
+    match self.network() {
+      Network::Bitcoin => "",
+      Network::Testnet => "testnet3",
+      Network::Signet => "signet",
+      Network::Regtest => "regtest",
+    }
+  }
+
+  pub(crate) fn join_with_core_data_dir(self, data_dir: &Path) -> PathBuf {
+    match self.core_data_dir_name() {
+      "" => data_dir.to_owned(),
+      name => data_dir.join(name),
+    }
+  }
 }
 
 impl Options {
-  pub(crate) fn max_index_size(&self) -> Bytes {
-    self.max_index_size.unwrap_or(match self.chain.network() {
-      Network::Regtest => Bytes::MIB * 10,
-      Network::Bitcoin | Network::Signet | Network::Testnet => Bytes::TIB,
-    })
+  // Resolves CLI flags, `ORD_`-prefixed environment variables, the config
+  // file, and `bitcoin.conf` into a single `Settings`, in that order of
+  // precedence.
+  fn settings(&self) -> Result<Settings> {
+    Settings::load(self)
+  }
+
+  pub(crate) fn chain(&self) -> Result<Chain> {
+    Ok(self.settings()?.chain.unwrap_or(Chain::Mainnet))
+  }
+
+  // Resolves the chain from the CLI flag or `ORD_CHAIN`, without touching
+  // the config file. Used by the locator accessors below (`data_dir`,
+  // `bitcoin_conf_values`), which can't call
+  // `Options::chain`/`Options::settings`, since the config file is itself
+  // found via `data_dir`.
+  fn cli_or_env_chain(&self) -> Result<Chain> {
+    if let Some(chain) = self.chain {
+      return Ok(chain);
+    }
+
+    match env::var("ORD_CHAIN") {
+      Ok(value) => <Chain as ValueEnum>::from_str(&value, true).map_err(|err| anyhow!(err)),
+      Err(_) => Ok(Chain::Mainnet),
+    }
+  }
+
+  // The config file is resolved independently of the rest of `Settings`,
+  // since it would otherwise have to locate itself.
+  fn config_path(&self) -> Result<Option<PathBuf>> {
+    if let Some(config) = &self.config {
+      return Ok(Some(config.clone()));
+    }
+
+    let config_dir = match &self.config_dir {
+      Some(config_dir) => config_dir.clone(),
+      None => self.data_dir()?,
+    };
+
+    let path = config_dir.join("config.yaml");
+
+    Ok(if path.is_file() { Some(path) } else { None })
   }
 
-  pub(crate) fn rpc_url(&self) -> String {
-    self
-      .rpc_url
-      .as_ref()
-      .unwrap_or(&format!(
+  pub(crate) fn max_index_size(&self) -> Result<Bytes> {
+    let settings = self.settings()?;
+
+    Ok(
+      settings
+        .max_index_size
+        .unwrap_or(match settings.chain.unwrap_or(Chain::Mainnet).network() {
+          Network::Regtest => Bytes::MIB * 10,
+          Network::Bitcoin | Network::Signet | Network::Testnet => Bytes::TIB,
+        }),
+    )
+  }
+
+  pub(crate) fn rpc_url(&self) -> Result<String> {
+    let settings = self.settings()?;
+
+    Ok(settings.rpc_url.unwrap_or_else(|| {
+      format!(
         "127.0.0.1:{}",
-        match self.chain.network() {
+        match settings.chain.unwrap_or(Chain::Mainnet).network() {
           Network::Bitcoin => "8332",
           Network::Regtest => "18443",
           Network::Signet => "38332",
           Network::Testnet => "18332",
         }
-      ))
-      .into()
+      )
+    }))
   }
 
   pub(crate) fn cookie_file(&self) -> Result<PathBuf> {
-    if let Some(cookie_file) = &self.cookie_file {
-      return Ok(cookie_file.clone());
+    let settings = self.settings()?;
+
+    if let Some(cookie_file) = settings.cookie_file {
+      return Ok(cookie_file);
+    }
+
+    let path = settings
+      .chain
+      .unwrap_or(Chain::Mainnet)
+      .join_with_core_data_dir(&self.bitcoin_data_dir()?);
+
+    Ok(path.join(".cookie"))
+  }
+
+  // `bitcoin_data_dir` is used to locate `bitcoin.conf`, so, like
+  // `data_dir`, it resolves only CLI flags and `ORD_`-prefixed environment
+  // variables, and cannot depend on the rest of `Settings` (the config
+  // file).
+  fn bitcoin_data_dir(&self) -> Result<PathBuf> {
+    if let Some(bitcoin_data_dir) = &self.bitcoin_data_dir {
+      return Ok(bitcoin_data_dir.clone());
     }
 
-    let path = if let Some(bitcoin_data_dir) = &self.bitcoin_data_dir {
-      bitcoin_data_dir.clone()
-    } else if cfg!(target_os = "linux") {
-      dirs::home_dir()
-        .ok_or_else(|| anyhow!("Failed to retrieve home dir"))?
-        .join(".bitcoin")
+    if let Some(bitcoin_data_dir) = env::var_os("ORD_BITCOIN_DATA_DIR") {
+      return Ok(PathBuf::from(bitcoin_data_dir));
+    }
+
+    if cfg!(target_os = "linux") {
+      Ok(
+        dirs::home_dir()
+          .ok_or_else(|| anyhow!("Failed to retrieve home dir"))?
+          .join(".bitcoin"),
+      )
     } else {
-      dirs::data_dir()
-        .ok_or_else(|| anyhow!("Failed to retrieve data dir"))?
-        .join("Bitcoin")
+      Ok(
+        dirs::data_dir()
+          .ok_or_else(|| anyhow!("Failed to retrieve data dir"))?
+          .join("Bitcoin"),
+      )
+    }
+  }
+
+  // `bitcoin.conf` is resolved independently of the rest of `Settings`,
+  // since it would otherwise have to locate itself.
+  fn bitcoin_conf_path(&self) -> Result<Option<PathBuf>> {
+    if let Some(bitcoin_conf) = &self.bitcoin_conf {
+      return Ok(Some(bitcoin_conf.clone()));
+    }
+
+    let path = self.bitcoin_data_dir()?.join("bitcoin.conf");
+
+    Ok(if path.is_file() { Some(path) } else { None })
+  }
+
+  // Parses `bitcoin.conf` as an INI file, scoped to the section
+  // (`[main]`/`[test]`/`[signet]`/`[regtest]`) for the chain given by
+  // `--chain` or `ORD_CHAIN` (see `cli_or_env_chain`), with top-level keys
+  // applying regardless of chain. A chain set only in the config file does
+  // not select the section.
+  fn bitcoin_conf_values(&self) -> Result<Option<BTreeMap<String, String>>> {
+    let path = match self.bitcoin_conf_path()? {
+      Some(path) => path,
+      None => return Ok(None),
     };
 
-    let path = self.chain.join_network_with_data_dir(&path);
+    let content = fs::read_to_string(&path)
+      .with_context(|| format!("failed to read `{}`", path.display()))?;
 
-    Ok(path.join(".cookie"))
+    let section = match self.cli_or_env_chain()?.network() {
+      Network::Bitcoin => "main",
+      Network::Testnet => "test",
+      Network::Signet => "signet",
+      Network::Regtest => "regtest",
+    };
+
+    let mut values = BTreeMap::new();
+    let mut current_section = None;
+
+    for line in content.lines() {
+      let line = line.split('#').next().unwrap().trim();
+
+      if line.is_empty() {
+        continue;
+      }
+
+      if let Some(name) = line
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+      {
+        current_section = Some(name.to_string());
+        continue;
+      }
+
+      let in_scope = match &current_section {
+        None => true,
+        Some(name) => name == section,
+      };
+
+      if !in_scope {
+        continue;
+      }
+
+      if let Some((key, value)) = line.split_once('=') {
+        values.insert(key.trim().to_string(), value.trim().to_string());
+      }
+    }
+
+    Ok(Some(values))
   }
 
-  pub(crate) fn data_dir(&self) -> Result<PathBuf> {
-    if let Some(data_dir) = &self.data_dir {
-      return Ok(data_dir.clone());
+  fn bitcoin_conf_rpc_url(&self) -> Result<Option<String>> {
+    Ok(self.bitcoin_conf_values()?.and_then(|values| {
+      let address = values.get("rpcconnect").map_or("127.0.0.1", String::as_str);
+      values.get("rpcport").map(|port| format!("{address}:{port}"))
+    }))
+  }
+
+  fn bitcoin_conf_rpc_auth(&self) -> Result<Option<RpcAuth>> {
+    Ok(
+      self
+        .bitcoin_conf_values()?
+        .and_then(|values| match (values.get("rpcuser"), values.get("rpcpassword")) {
+          (Some(username), Some(password)) => {
+            Some(RpcAuth::UserPass(username.clone(), password.clone()))
+          }
+          _ => None,
+        }),
+    )
+  }
+
+  // Ordinal indexing requires a fully-indexed node; refuse to proceed if
+  // `bitcoin.conf` is present but doesn't request one.
+  fn require_txindex(&self) -> Result {
+    let Some(values) = self.bitcoin_conf_values()? else {
+      return Ok(());
+    };
+
+    if values.get("txindex").map(String::as_str) != Some("1") {
+      bail!(
+        "`{}` does not set `txindex=1`; ord requires a Bitcoin Core node with `-txindex`",
+        self.bitcoin_conf_path()?.unwrap().display(),
+      );
     }
 
-    let path = dirs::data_dir()
-      .ok_or_else(|| anyhow!("Failed to retrieve data dir"))?
-      .join("ord");
+    Ok(())
+  }
 
-    let path = self.chain.join_network_with_data_dir(&path);
+  // `data_dir` is used to locate the config file, so, like
+  // `bitcoin_data_dir`, it resolves only CLI flags and `ORD_`-prefixed environment
+  // variables, and cannot depend on the rest of `Settings` (the config
+  // file).
+  pub(crate) fn data_dir(&self) -> Result<PathBuf> {
+    let path = if let Some(data_dir) = &self.data_dir {
+      data_dir.clone()
+    } else if let Some(data_dir) = env::var_os("ORD_DATA_DIR") {
+      PathBuf::from(data_dir)
+    } else {
+      let path = dirs::data_dir()
+        .ok_or_else(|| anyhow!("Failed to retrieve data dir"))?
+        .join("ord");
+
+      self.cli_or_env_chain()?.join_network_with_data_dir(&path)
+    };
 
     if let Err(err) = fs::create_dir_all(&path) {
       bail!("Failed to create data dir `{}`: {err}", path.display());
@@ -117,22 +473,125 @@ impl Options {
     Ok(path)
   }
 
-  pub(crate) fn bitcoin_rpc_client(&self) -> Result<Client> {
-    let cookie_file = self.cookie_file()?;
-    let rpc_url = self.rpc_url();
-    log::info!(
-      "Connecting to Bitcoin Core RPC server at {rpc_url} using credentials from `{}`",
-      cookie_file.display()
+  // Resolves Bitcoin Core RPC credentials by trying, in order: explicit
+  // `--bitcoin-rpc-username`/`--bitcoin-rpc-password` flags, the cookie
+  // file, `rpcuser`/`rpcpassword` in `bitcoin.conf`, a `.env` file in the
+  // data dir, and finally `ORD_BITCOIN_RPC_USERNAME`/
+  // `ORD_BITCOIN_RPC_PASSWORD` environment variables.
+  fn rpc_auth(&self) -> Result<RpcAuth> {
+    if let (Some(username), Some(password)) = (
+      self.bitcoin_rpc_username.clone(),
+      self.bitcoin_rpc_password.clone(),
+    ) {
+      return Ok(RpcAuth::UserPass(username, password));
+    }
+
+    let mut attempted = Vec::new();
+
+    match self.cookie_file() {
+      Ok(cookie_file) if cookie_file.is_file() => return Ok(RpcAuth::Cookie(cookie_file)),
+      Ok(cookie_file) => attempted.push(format!(
+        "cookie file `{}` does not exist",
+        cookie_file.display()
+      )),
+      Err(err) => attempted.push(format!("failed to locate cookie file: {err}")),
+    }
+
+    match self.bitcoin_conf_rpc_auth() {
+      Ok(Some(auth)) => return Ok(auth),
+      Ok(None) => {
+        if self.bitcoin_conf_path()?.is_some() {
+          attempted.push("bitcoin.conf has no `rpcuser`/`rpcpassword`".into());
+        }
+      }
+      Err(err) => attempted.push(format!("failed to read bitcoin.conf: {err}")),
+    }
+
+    match self.rpc_auth_from_dotenv() {
+      Ok(Some(auth)) => return Ok(auth),
+      Ok(None) => attempted.push(
+        "no `.env` file with `RPC_USER` and `RPC_PASSWORD` found in data dir".into(),
+      ),
+      Err(err) => attempted.push(format!("failed to read `.env` file: {err}")),
+    }
+
+    if let (Ok(username), Ok(password)) = (
+      env::var("ORD_BITCOIN_RPC_USERNAME"),
+      env::var("ORD_BITCOIN_RPC_PASSWORD"),
+    ) {
+      return Ok(RpcAuth::UserPass(username, password));
+    }
+
+    attempted
+      .push("`ORD_BITCOIN_RPC_USERNAME`/`ORD_BITCOIN_RPC_PASSWORD` not set".into());
+
+    bail!(
+      "failed to find Bitcoin Core RPC credentials, tried:\n{}",
+      attempted.join("\n"),
     );
+  }
 
-    Client::new(&rpc_url, Auth::CookieFile(cookie_file))
+  fn rpc_auth_from_dotenv(&self) -> Result<Option<RpcAuth>> {
+    let path = self.data_dir()?.join(".env");
+
+    if !path.is_file() {
+      return Ok(None);
+    }
+
+    let dotenv = fs::read_to_string(&path)
+      .with_context(|| format!("failed to read `.env` file `{}`", path.display()))?;
+
+    let mut username = None;
+    let mut password = None;
+
+    for line in dotenv.lines() {
+      if let Some(value) = line.trim().strip_prefix("RPC_USER=") {
+        username = Some(value.trim().to_string());
+      } else if let Some(value) = line.trim().strip_prefix("RPC_PASSWORD=") {
+        password = Some(value.trim().to_string());
+      }
+    }
+
+    Ok(match (username, password) {
+      (Some(username), Some(password)) => Some(RpcAuth::UserPass(username, password)),
+      _ => None,
+    })
+  }
+
+  pub(crate) fn bitcoin_rpc_client(&self) -> Result<Client> {
+    self.require_txindex()?;
+
+    let rpc_url = self.rpc_url()?;
+    let auth = self.rpc_auth()?;
+
+    log::info!("Connecting to Bitcoin Core RPC server at {rpc_url} using {auth}");
+
+    Client::new(&rpc_url, auth.into_auth())
       .context("Failed to connect to Bitcoin Core RPC at {rpc_url}")
   }
 }
 
 #[cfg(test)]
 mod tests {
-  use {super::*, std::path::Path};
+  use {
+    super::*,
+    std::{
+      path::Path,
+      sync::{Mutex, MutexGuard},
+    },
+    tempfile::TempDir,
+  };
+
+  // `ORD_*` env vars are process-global, but `#[test]`s run concurrently on
+  // separate threads, so any test that sets one must hold this for its
+  // duration to avoid racing the others.
+  static ENV_MUTEX: Mutex<()> = Mutex::new(());
+
+  fn lock_env() -> MutexGuard<'static, ()> {
+    ENV_MUTEX
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+  }
 
   #[test]
   fn max_index_size_defaults() {
@@ -140,7 +599,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes::TIB
     );
 
@@ -148,7 +608,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--chain=mainnet", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes::TIB
     );
 
@@ -156,7 +617,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--chain=signet", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes::TIB
     );
 
@@ -164,7 +626,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--chain=testnet", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes::TIB
     );
 
@@ -172,7 +635,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--chain=regtest", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes::MIB * 10
     );
   }
@@ -183,7 +647,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--max-index-size=1", "index"])
         .unwrap()
         .options
-        .max_index_size(),
+        .max_index_size()
+        .unwrap(),
       Bytes(1),
     );
   }
@@ -194,7 +659,8 @@ mod tests {
       Arguments::try_parse_from(&["ord", "--rpc-url=127.0.0.1:1234", "--chain=signet", "index"])
         .unwrap()
         .options
-        .rpc_url(),
+        .rpc_url()
+        .unwrap(),
       "127.0.0.1:1234"
     );
   }
@@ -215,7 +681,7 @@ mod tests {
   fn use_default_network() {
     let arguments = Arguments::try_parse_from(&["ord", "index"]).unwrap();
 
-    assert_eq!(arguments.options.rpc_url(), "127.0.0.1:8332");
+    assert_eq!(arguments.options.rpc_url().unwrap(), "127.0.0.1:8332");
 
     assert!(arguments
       .options
@@ -228,7 +694,7 @@ mod tests {
   fn uses_network_defaults() {
     let arguments = Arguments::try_parse_from(&["ord", "--chain=signet", "index"]).unwrap();
 
-    assert_eq!(arguments.options.rpc_url(), "127.0.0.1:38332");
+    assert_eq!(arguments.options.rpc_url().unwrap(), "127.0.0.1:38332");
 
     assert!(arguments
       .options
@@ -309,6 +775,60 @@ mod tests {
     assert!(data_dir.ends_with("/ord/signet"));
   }
 
+  #[test]
+  fn env_var_overrides_default_data_dir() {
+    let _guard = lock_env();
+
+    env::set_var("ORD_DATA_DIR", "/tmp/ord-data-dir-from-env");
+
+    let result = Arguments::try_parse_from(&["ord", "index"])
+      .unwrap()
+      .options
+      .data_dir();
+
+    env::remove_var("ORD_DATA_DIR");
+
+    assert_eq!(
+      result.unwrap().display().to_string(),
+      "/tmp/ord-data-dir-from-env"
+    );
+  }
+
+  #[test]
+  fn env_var_overrides_default_bitcoin_data_dir() {
+    let _guard = lock_env();
+
+    env::set_var("ORD_BITCOIN_DATA_DIR", "/tmp/bitcoin-data-dir-from-env");
+
+    let result = Arguments::try_parse_from(&["ord", "index"])
+      .unwrap()
+      .options
+      .cookie_file();
+
+    env::remove_var("ORD_BITCOIN_DATA_DIR");
+
+    assert_eq!(
+      result.unwrap().display().to_string(),
+      "/tmp/bitcoin-data-dir-from-env/.cookie"
+    );
+  }
+
+  #[test]
+  fn cli_chain_flag_overrides_ord_chain_env_var() {
+    let _guard = lock_env();
+
+    env::set_var("ORD_CHAIN", "testnet");
+
+    let result = Arguments::try_parse_from(&["ord", "--chain=mainnet", "index"])
+      .unwrap()
+      .options
+      .chain();
+
+    env::remove_var("ORD_CHAIN");
+
+    assert_eq!(result.unwrap(), Chain::Mainnet);
+  }
+
   #[test]
   fn network_accepts_aliases() {
     fn check_network_alias(alias: &str, suffix: &str) {
@@ -330,4 +850,367 @@ mod tests {
     check_network_alias("test", "ord/testnet");
     check_network_alias("testnet", "ord/testnet");
   }
+
+  #[test]
+  fn env_var_overrides_default_rpc_url() {
+    let _guard = lock_env();
+
+    env::set_var("ORD_RPC_URL", "127.0.0.1:4321");
+
+    let result = Arguments::try_parse_from(&["ord", "index"])
+      .unwrap()
+      .options
+      .rpc_url();
+
+    env::remove_var("ORD_RPC_URL");
+
+    assert_eq!(result.unwrap(), "127.0.0.1:4321");
+  }
+
+  #[test]
+  fn cli_flag_overrides_env_var() {
+    let _guard = lock_env();
+
+    env::set_var("ORD_RPC_URL", "127.0.0.1:4321");
+
+    let result = Arguments::try_parse_from(&["ord", "--rpc-url=127.0.0.1:1234", "index"])
+      .unwrap()
+      .options
+      .rpc_url();
+
+    env::remove_var("ORD_RPC_URL");
+
+    assert_eq!(result.unwrap(), "127.0.0.1:1234");
+  }
+
+  #[test]
+  fn config_file_sets_rpc_url() {
+    let tempdir = TempDir::new().unwrap();
+
+    fs::write(tempdir.path().join("config.yaml"), "rpc-url: 127.0.0.1:5678\n").unwrap();
+
+    let result = Arguments::try_parse_from(&[
+      "ord",
+      "--config-dir",
+      tempdir.path().to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .rpc_url();
+
+    assert_eq!(result.unwrap(), "127.0.0.1:5678");
+  }
+
+  #[test]
+  fn env_var_overrides_config_file() {
+    let _guard = lock_env();
+
+    let tempdir = TempDir::new().unwrap();
+
+    fs::write(tempdir.path().join("config.yaml"), "rpc-url: 127.0.0.1:5678\n").unwrap();
+
+    env::set_var("ORD_RPC_URL", "127.0.0.1:4321");
+
+    let result = Arguments::try_parse_from(&[
+      "ord",
+      "--config-dir",
+      tempdir.path().to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .rpc_url();
+
+    env::remove_var("ORD_RPC_URL");
+
+    assert_eq!(result.unwrap(), "127.0.0.1:4321");
+  }
+
+  #[test]
+  fn config_file_rejects_unknown_fields() {
+    let tempdir = TempDir::new().unwrap();
+
+    fs::write(
+      tempdir.path().join("config.yaml"),
+      "not-a-real-setting: true\n",
+    )
+    .unwrap();
+
+    let result = Arguments::try_parse_from(&[
+      "ord",
+      "--config-dir",
+      tempdir.path().to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .rpc_url();
+
+    assert!(result.is_err());
+  }
+
+  #[test]
+  fn explicit_username_and_password_take_priority() {
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--bitcoin-rpc-username=foo",
+      "--bitcoin-rpc-password=bar",
+      "--cookie-file=/foo/bar",
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(
+      arguments.options.rpc_auth().unwrap(),
+      RpcAuth::UserPass("foo".into(), "bar".into()),
+    );
+  }
+
+  #[test]
+  fn falls_back_to_cookie_file_when_present() {
+    let tempdir = TempDir::new().unwrap();
+    let cookie_file = tempdir.path().join(".cookie");
+    fs::write(&cookie_file, "foo:bar").unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--cookie-file",
+      cookie_file.to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(
+      arguments.options.rpc_auth().unwrap(),
+      RpcAuth::Cookie(cookie_file),
+    );
+  }
+
+  #[test]
+  fn falls_back_to_dotenv_file_when_cookie_file_is_missing() {
+    let tempdir = TempDir::new().unwrap();
+
+    fs::write(
+      tempdir.path().join(".env"),
+      "RPC_USER=foo\nRPC_PASSWORD=bar\n",
+    )
+    .unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--data-dir",
+      tempdir.path().to_str().unwrap(),
+      "--cookie-file",
+      tempdir.path().join(".cookie").to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(
+      arguments.options.rpc_auth().unwrap(),
+      RpcAuth::UserPass("foo".into(), "bar".into()),
+    );
+  }
+
+  #[test]
+  fn falls_back_to_env_vars_when_nothing_else_matches() {
+    let _guard = lock_env();
+
+    let tempdir = TempDir::new().unwrap();
+
+    env::set_var("ORD_BITCOIN_RPC_USERNAME", "foo");
+    env::set_var("ORD_BITCOIN_RPC_PASSWORD", "bar");
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--data-dir",
+      tempdir.path().to_str().unwrap(),
+      "--cookie-file",
+      tempdir.path().join(".cookie").to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    let result = arguments.options.rpc_auth();
+
+    env::remove_var("ORD_BITCOIN_RPC_USERNAME");
+    env::remove_var("ORD_BITCOIN_RPC_PASSWORD");
+
+    assert_eq!(result.unwrap(), RpcAuth::UserPass("foo".into(), "bar".into()));
+  }
+
+  #[test]
+  fn rpc_auth_errors_when_nothing_matches() {
+    let tempdir = TempDir::new().unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--data-dir",
+      tempdir.path().to_str().unwrap(),
+      "--cookie-file",
+      tempdir.path().join(".cookie").to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert!(arguments.options.rpc_auth().is_err());
+  }
+
+  #[test]
+  fn bitcoin_conf_supplies_rpc_url_and_auth() {
+    let tempdir = TempDir::new().unwrap();
+    let bitcoin_conf = tempdir.path().join("bitcoin.conf");
+
+    fs::write(
+      &bitcoin_conf,
+      "txindex=1\nrpcport=9000\nrpcuser=foo\nrpcpassword=bar\n",
+    )
+    .unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--bitcoin-conf",
+      bitcoin_conf.to_str().unwrap(),
+      "--cookie-file",
+      tempdir.path().join(".cookie").to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(arguments.options.rpc_url().unwrap(), "127.0.0.1:9000");
+    assert_eq!(
+      arguments.options.rpc_auth().unwrap(),
+      RpcAuth::UserPass("foo".into(), "bar".into()),
+    );
+  }
+
+  #[test]
+  fn bitcoin_conf_scopes_values_by_chain_section() {
+    let tempdir = TempDir::new().unwrap();
+    let bitcoin_conf = tempdir.path().join("bitcoin.conf");
+
+    fs::write(
+      &bitcoin_conf,
+      "txindex=1\nrpcport=8332\n\n[test]\nrpcport=18332\n",
+    )
+    .unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--chain=testnet",
+      "--bitcoin-conf",
+      bitcoin_conf.to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(arguments.options.rpc_url().unwrap(), "127.0.0.1:18332");
+  }
+
+  #[test]
+  fn bitcoin_conf_honors_explicit_main_section() {
+    let tempdir = TempDir::new().unwrap();
+    let bitcoin_conf = tempdir.path().join("bitcoin.conf");
+
+    fs::write(
+      &bitcoin_conf,
+      "txindex=1\n\n[main]\nrpcport=8332\n\n[test]\nrpcport=18332\n",
+    )
+    .unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--bitcoin-conf",
+      bitcoin_conf.to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert_eq!(arguments.options.rpc_url().unwrap(), "127.0.0.1:8332");
+  }
+
+  #[test]
+  fn bitcoin_conf_section_follows_ord_chain_env_var() {
+    let _guard = lock_env();
+
+    let tempdir = TempDir::new().unwrap();
+    let bitcoin_conf = tempdir.path().join("bitcoin.conf");
+
+    fs::write(
+      &bitcoin_conf,
+      "txindex=1\nrpcport=8332\n\n[signet]\nrpcport=38332\n",
+    )
+    .unwrap();
+
+    env::set_var("ORD_CHAIN", "signet");
+
+    let result = Arguments::try_parse_from(&[
+      "ord",
+      "--bitcoin-conf",
+      bitcoin_conf.to_str().unwrap(),
+      "index",
+    ])
+    .unwrap()
+    .options
+    .rpc_url();
+
+    env::remove_var("ORD_CHAIN");
+
+    assert_eq!(result.unwrap(), "127.0.0.1:38332");
+  }
+
+  #[test]
+  fn missing_txindex_is_an_error() {
+    let tempdir = TempDir::new().unwrap();
+    let bitcoin_conf = tempdir.path().join("bitcoin.conf");
+
+    fs::write(&bitcoin_conf, "rpcport=9000\n").unwrap();
+
+    let arguments = Arguments::try_parse_from(&[
+      "ord",
+      "--bitcoin-conf",
+      bitcoin_conf.to_str().unwrap(),
+      "index",
+    ])
+    .unwrap();
+
+    assert!(arguments.options.bitcoin_rpc_client().is_err());
+  }
+
+  #[test]
+  fn cookie_file_uses_core_data_dir_names() {
+    fn check(alias: &str, suffix: &str) {
+      let cookie_file = Arguments::try_parse_from(&[
+        "ord",
+        "--chain",
+        alias,
+        "--bitcoin-data-dir=foo",
+        "index",
+      ])
+      .unwrap()
+      .options
+      .cookie_file()
+      .unwrap()
+      .display()
+      .to_string();
+
+      assert!(cookie_file.ends_with(suffix), "{cookie_file}");
+    }
+
+    check("main", "foo/.cookie");
+    check("mainnet", "foo/.cookie");
+    check("testnet", "foo/testnet3/.cookie");
+    check("test", "foo/testnet3/.cookie");
+    check("signet", "foo/signet/.cookie");
+    check("regtest", "foo/regtest/.cookie");
+  }
+
+  #[test]
+  fn core_data_dir_name_matches_bitcoin_core_layout() {
+    assert_eq!(Chain::Mainnet.core_data_dir_name(), "");
+    assert_eq!(Chain::Testnet.core_data_dir_name(), "testnet3");
+    assert_eq!(Chain::Signet.core_data_dir_name(), "signet");
+    assert_eq!(Chain::Regtest.core_data_dir_name(), "regtest");
+  }
 }
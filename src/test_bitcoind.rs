@@ -0,0 +1,73 @@
+// Optional integration-test harness that spins up a throwaway regtest
+// `bitcoind`, pointed at by a populated `Options`, and tears the node down
+// on drop. Gated behind the `test-bitcoind` feature so ordinary builds
+// don't pull in the harness or its `bitcoind` dependency.
+//
+// `BitcoinNode` and `Options::test_regtest` are declared `pub`, but
+// `Options` itself is `pub(crate)`, so `test_regtest` can only be called
+// from within this crate, not from a separately compiled `tests/` crate.
+//
+// BLOCKED: this file is not yet reachable from the rest of the crate. It
+// needs a `#[cfg(feature = "test-bitcoind")] mod test_bitcoind;` declaration
+// in the crate root, alongside the `mod options;` that this same root must
+// already carry for `src/options.rs` to compile. Neither the crate root nor
+// any other module is part of this source tree, so that declaration can't
+// be added here; wiring this module in is left to whoever owns `lib.rs`.
+#![cfg(feature = "test-bitcoind")]
+
+use {super::*, bitcoind::BitcoinD};
+
+pub struct BitcoinNode {
+  bitcoind: BitcoinD,
+}
+
+impl BitcoinNode {
+  fn new() -> Result<Self> {
+    let exe = bitcoind::downloaded_exe_path().context(
+      "failed to find a downloaded `bitcoind` binary; the `test-bitcoind` feature must enable \
+       a `bitcoind` version feature (e.g. `25_1`)",
+    )?;
+
+    let bitcoind = BitcoinD::new(exe).context("failed to start regtest bitcoind")?;
+
+    Ok(Self { bitcoind })
+  }
+
+  pub fn rpc_url(&self) -> String {
+    self.bitcoind.params.rpc_socket.to_string()
+  }
+
+  pub fn cookie_file(&self) -> &Path {
+    &self.bitcoind.params.cookie_file
+  }
+
+  pub fn client(&self) -> &bitcoincore_rpc::Client {
+    &self.bitcoind.client
+  }
+}
+
+impl Options {
+  // Starts a regtest `bitcoind` and returns `Options` resolved to talk to
+  // it, so integration tests can mine blocks, create inscriptions, and
+  // exercise `bitcoin_rpc_client` end-to-end instead of against mocks.
+  pub fn test_regtest() -> (BitcoinNode, Options) {
+    let node = BitcoinNode::new().expect("failed to start regtest bitcoind");
+
+    let options = Arguments::try_parse_from([
+      "ord",
+      "--chain=regtest",
+      "--rpc-url",
+      &node.rpc_url(),
+      "--cookie-file",
+      node
+        .cookie_file()
+        .to_str()
+        .expect("cookie file path is not valid UTF-8"),
+      "index",
+    ])
+    .expect("failed to construct regtest options")
+    .options;
+
+    (node, options)
+  }
+}